@@ -0,0 +1,91 @@
+//! Diagnostics emitted while compiling a set of sources.
+
+mod fatal;
+mod warning;
+
+pub use self::fatal::{FatalDiagnostic, FatalDiagnosticKind};
+pub use self::warning::{
+    LintLevel, LintLevels, LintOutcome, WarningDiagnostic, WarningDiagnosticKind,
+};
+
+use crate::SourceId;
+
+/// A single diagnostic emitted during a compilation.
+#[derive(Debug)]
+pub enum Diagnostic {
+    /// A fatal diagnostic that aborts the build.
+    Fatal(FatalDiagnostic),
+    /// A non-fatal warning.
+    Warning(WarningDiagnostic),
+}
+
+/// The collection of diagnostics emitted during a compilation, together with
+/// the lint-level configuration that governs how warnings are treated.
+#[derive(Debug, Default)]
+pub struct Diagnostics {
+    diagnostics: Vec<Diagnostic>,
+    lint_levels: LintLevels,
+    has_error: bool,
+}
+
+impl Diagnostics {
+    /// Construct a new empty collection of diagnostics.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The configured lint levels for this build unit.
+    pub fn lint_levels(&self) -> &LintLevels {
+        &self.lint_levels
+    }
+
+    /// Mutable access to the lint levels so they can be configured.
+    pub fn lint_levels_mut(&mut self) -> &mut LintLevels {
+        &mut self.lint_levels
+    }
+
+    /// Indicates if the collection contains any fatal diagnostics.
+    pub fn has_error(&self) -> bool {
+        self.has_error
+    }
+
+    /// Indicates if the collection is empty.
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    /// The diagnostics in the collection, in the order they were reported.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// Report a fatal diagnostic.
+    pub fn fatal(&mut self, source_id: SourceId, kind: FatalDiagnosticKind) {
+        self.has_error = true;
+        self.diagnostics.push(Diagnostic::Fatal(FatalDiagnostic {
+            source_id,
+            kind: Box::new(kind),
+        }));
+    }
+
+    /// Report a warning, resolving it against the configured lint levels.
+    ///
+    /// A `deny`-level lint is promoted to a fatal diagnostic, an `allow`-level
+    /// lint is discarded, and everything else is recorded as a warning.
+    pub fn warning(&mut self, source_id: SourceId, kind: WarningDiagnosticKind) {
+        let warning = WarningDiagnostic {
+            source_id,
+            kind: Box::new(kind),
+        };
+
+        match warning.resolve(&self.lint_levels) {
+            LintOutcome::Allowed => {}
+            LintOutcome::Warn(warning) => {
+                self.diagnostics.push(Diagnostic::Warning(warning));
+            }
+            LintOutcome::Deny(kind) => {
+                self.fatal(source_id, kind);
+            }
+        }
+    }
+}
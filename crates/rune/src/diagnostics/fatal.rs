@@ -1,4 +1,5 @@
 use crate::compiling::LinkerError;
+use crate::diagnostics::WarningDiagnostic;
 use crate::{BuildError, CompileError, ParseError, QueryError, SourceId};
 use std::error;
 use std::fmt;
@@ -76,6 +77,13 @@ pub enum FatalDiagnosticKind {
         #[source]
         BuildError,
     ),
+    /// A warning that was promoted to a fatal error by a `deny` lint level.
+    #[error("lint error")]
+    Warning(
+        #[from]
+        #[source]
+        WarningDiagnostic,
+    ),
     /// An internal error.
     #[error("internal error: {0}")]
     Internal(&'static str),
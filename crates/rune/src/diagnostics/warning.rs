@@ -0,0 +1,147 @@
+use crate::ast::Span;
+use crate::diagnostics::FatalDiagnosticKind;
+use crate::SourceId;
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+use thiserror::Error;
+
+/// The level a lint is configured at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintLevel {
+    /// The lint is silenced; matching warnings are discarded.
+    Allow,
+    /// The lint is reported as a non-fatal warning (the default).
+    Warn,
+    /// The lint is promoted to a fatal error.
+    Deny,
+}
+
+impl Default for LintLevel {
+    fn default() -> Self {
+        LintLevel::Warn
+    }
+}
+
+/// A configurable map from lint identifiers to [`LintLevel`]s, used to
+/// `allow`/`warn`/`deny` individual lints on the build unit.
+#[derive(Debug, Default)]
+pub struct LintLevels {
+    levels: HashMap<&'static str, LintLevel>,
+}
+
+impl LintLevels {
+    /// Set the level of the given lint.
+    pub fn set(&mut self, lint: &'static str, level: LintLevel) {
+        self.levels.insert(lint, level);
+    }
+
+    /// Get the configured level of the given lint, defaulting to
+    /// [`LintLevel::Warn`].
+    pub fn level(&self, lint: &'static str) -> LintLevel {
+        self.levels.get(lint).copied().unwrap_or_default()
+    }
+}
+
+/// The outcome of resolving a [`WarningDiagnostic`] against the configured
+/// [`LintLevels`].
+#[derive(Debug)]
+pub enum LintOutcome {
+    /// The lint is allowed; the warning is discarded.
+    Allowed,
+    /// The warning is reported as-is.
+    Warn(WarningDiagnostic),
+    /// The warning is promoted to a fatal diagnostic.
+    Deny(FatalDiagnosticKind),
+}
+
+/// Warning diagnostic emitted during compilation. Warning diagnostics indicate
+/// a recoverable issue that does not abort the build.
+#[derive(Debug)]
+pub struct WarningDiagnostic {
+    /// The source id of the warning.
+    pub(super) source_id: SourceId,
+    /// The kind of the warning.
+    pub(super) kind: Box<WarningDiagnosticKind>,
+}
+
+impl WarningDiagnostic {
+    /// The source id where the warning originates from.
+    pub fn source_id(&self) -> SourceId {
+        self.source_id
+    }
+
+    /// The kind of the warning.
+    pub fn kind(&self) -> &WarningDiagnosticKind {
+        &self.kind
+    }
+
+    /// Convert into the kind of the warning.
+    pub fn into_kind(self) -> WarningDiagnosticKind {
+        *self.kind
+    }
+
+    /// Resolve this warning against the configured lint levels, promoting it to
+    /// a fatal diagnostic when its lint is set to `deny` and discarding it when
+    /// set to `allow`.
+    pub fn resolve(self, levels: &LintLevels) -> LintOutcome {
+        match levels.level(self.kind.lint()) {
+            LintLevel::Allow => LintOutcome::Allowed,
+            LintLevel::Warn => LintOutcome::Warn(self),
+            LintLevel::Deny => LintOutcome::Deny(FatalDiagnosticKind::Warning(self)),
+        }
+    }
+}
+
+impl fmt::Display for WarningDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.kind.fmt(f)
+    }
+}
+
+impl error::Error for WarningDiagnostic {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        self.kind.source()
+    }
+}
+
+#[allow(missing_docs)]
+#[derive(Debug, Error)]
+pub enum WarningDiagnosticKind {
+    #[error("use of deprecated item: {message}")]
+    Deprecated { span: Span, message: String },
+    #[error("unused binding")]
+    Unused { span: Span },
+    #[error("unreachable code")]
+    Unreachable { span: Span },
+    /// A construct that compiles today but is slated to become an error, kept
+    /// separate from ordinary warnings so tooling can enumerate it on its own.
+    #[error("future incompatibility ({lint}): {message}")]
+    FutureIncompatible {
+        lint: &'static str,
+        span: Span,
+        message: String,
+    },
+}
+
+impl WarningDiagnosticKind {
+    /// The lint identifier this warning is associated with.
+    pub fn lint(&self) -> &'static str {
+        match self {
+            WarningDiagnosticKind::Deprecated { .. } => "deprecated",
+            WarningDiagnosticKind::Unused { .. } => "unused",
+            WarningDiagnosticKind::Unreachable { .. } => "unreachable_code",
+            WarningDiagnosticKind::FutureIncompatible { lint, .. } => lint,
+        }
+    }
+
+    /// The span the warning points at.
+    pub fn span(&self) -> Span {
+        match self {
+            WarningDiagnosticKind::Deprecated { span, .. } => *span,
+            WarningDiagnosticKind::Unused { span } => *span,
+            WarningDiagnosticKind::Unreachable { span } => *span,
+            WarningDiagnosticKind::FutureIncompatible { span, .. } => *span,
+        }
+    }
+}
@@ -0,0 +1,14 @@
+//! Public packages that can be used to extend the Rune language with
+//! functionality from the standard library.
+
+pub mod channel;
+pub mod future;
+
+use crate::{Context, ContextError};
+
+/// Install all default standard library modules into the given context.
+pub(crate) fn install(context: &mut Context) -> Result<(), ContextError> {
+    context.install(&future::module()?)?;
+    context.install(&channel::module()?)?;
+    Ok(())
+}
@@ -0,0 +1,378 @@
+//! The `std::channel` module.
+
+use std::collections::VecDeque;
+use std::future::Future as StdFuture;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+use crate::runtime::{Future, Value, VmResult};
+use crate::{Any, ContextError, Module};
+
+/// Construct the `std::channel` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::with_crate_item("std", ["channel"])?;
+    module.ty::<Sender>()?;
+    module.ty::<Receiver>()?;
+    module.ty::<OneshotSender>()?;
+    module.ty::<OneshotReceiver>()?;
+
+    module
+        .function("channel", channel)
+        .build()?
+        .args(0)
+        .docs([
+            "Create an unbounded multi-producer/single-consumer channel.",
+            "",
+            "Returns a pair `(sender, receiver)`. The sender can be cloned so that",
+            "multiple producers feed the single receiver.",
+            "",
+            "# Examples",
+            "",
+            "```rune",
+            "let (tx, rx) = std::channel::channel();",
+            "tx.send(42).await?;",
+            "assert_eq!(rx.recv().await, Some(42));",
+            "# Ok::<_, i64>(())",
+            "```",
+        ])?;
+
+    module
+        .function("bounded", bounded)
+        .build()?
+        .args(1)
+        .argument_types([None])?
+        .docs([
+            "Create a bounded multi-producer/single-consumer channel.",
+            "",
+            "Like [`channel`], but [`Sender::send`] resolves only once there is",
+            "room for the value in the buffer, providing backpressure.",
+        ])?;
+
+    module
+        .function("oneshot", oneshot)
+        .build()?
+        .args(0)
+        .docs([
+            "Create a oneshot channel for delivering a single value.",
+            "",
+            "Returns a pair `(sender, receiver)`. The sender delivers exactly one",
+            "value, after which the channel is closed.",
+        ])?;
+
+    module.associated_function("clone", Sender::clone)?;
+    module.associated_function("send", Sender::send)?;
+    module.associated_function("recv", Receiver::recv)?;
+    module.associated_function("send", OneshotSender::send)?;
+    module.associated_function("recv", OneshotReceiver::recv)?;
+
+    Ok(module)
+}
+
+/// Shared state backing a [`Sender`]/[`Receiver`] pair.
+#[derive(Debug)]
+struct Shared {
+    /// Buffered values waiting to be received.
+    queue: VecDeque<Value>,
+    /// Buffer capacity, or `None` when unbounded.
+    capacity: Option<usize>,
+    /// Number of live senders.
+    senders: usize,
+    /// Whether the receiver is still alive.
+    receiver: bool,
+    /// Wakers parked waiting to receive a value.
+    recv_wakers: Vec<Waker>,
+    /// Wakers parked waiting for buffer space.
+    send_wakers: Vec<Waker>,
+}
+
+impl Shared {
+    fn new(capacity: Option<usize>) -> Arc<Mutex<Self>> {
+        Arc::new(Mutex::new(Shared {
+            queue: VecDeque::new(),
+            capacity,
+            senders: 1,
+            receiver: true,
+            recv_wakers: Vec::new(),
+            send_wakers: Vec::new(),
+        }))
+    }
+
+    fn has_room(&self) -> bool {
+        match self.capacity {
+            Some(capacity) => self.queue.len() < capacity,
+            None => true,
+        }
+    }
+
+    fn wake_receivers(&mut self) {
+        for waker in self.recv_wakers.drain(..) {
+            waker.wake();
+        }
+    }
+
+    fn wake_senders(&mut self) {
+        for waker in self.send_wakers.drain(..) {
+            waker.wake();
+        }
+    }
+}
+
+/// The sending half of a channel.
+#[derive(Debug, Any)]
+#[rune(item = ::std::channel)]
+pub struct Sender {
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl Sender {
+    /// Send a value, returning a future that resolves once the value has been
+    /// buffered. Errors if the receiver has been dropped.
+    fn send(&self, value: Value) -> VmResult<Value> {
+        VmResult::Ok(vm_try!(Value::try_from(vm_try!(Future::new(SendFuture {
+            shared: self.shared.clone(),
+            value: Some(value),
+        })))))
+    }
+}
+
+impl Clone for Sender {
+    fn clone(&self) -> Self {
+        self.shared.lock().unwrap().senders += 1;
+        Sender {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl Drop for Sender {
+    fn drop(&mut self) {
+        let mut shared = self.shared.lock().unwrap();
+        shared.senders -= 1;
+        if shared.senders == 0 {
+            // Last sender gone: wake the receiver so it observes closure.
+            shared.wake_receivers();
+        }
+    }
+}
+
+/// The receiving half of a channel.
+#[derive(Debug, Any)]
+#[rune(item = ::std::channel)]
+pub struct Receiver {
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl Receiver {
+    /// Receive the next value, returning a future that resolves to `Some(value)`
+    /// or `None` once the channel is closed and drained.
+    fn recv(&self) -> VmResult<Value> {
+        VmResult::Ok(vm_try!(Value::try_from(vm_try!(Future::new(RecvFuture {
+            shared: self.shared.clone(),
+        })))))
+    }
+}
+
+impl Drop for Receiver {
+    fn drop(&mut self) {
+        let mut shared = self.shared.lock().unwrap();
+        shared.receiver = false;
+        // Wake any senders blocked on backpressure so they observe closure.
+        shared.wake_senders();
+    }
+}
+
+/// Future produced by [`Sender::send`].
+struct SendFuture {
+    shared: Arc<Mutex<Shared>>,
+    value: Option<Value>,
+}
+
+impl StdFuture for SendFuture {
+    type Output = VmResult<Value>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut shared = self.shared.lock().unwrap();
+
+        if !shared.receiver {
+            // Hand the value back to the caller inside the error.
+            let value = match self.value.take() {
+                Some(value) => value,
+                None => match Value::empty() {
+                    VmResult::Ok(value) => value,
+                    VmResult::Err(error) => return Poll::Ready(VmResult::Err(error)),
+                },
+            };
+            return Poll::Ready(Value::result(Err(value)));
+        }
+
+        if shared.has_room() {
+            let value = self
+                .value
+                .take()
+                .expect("send future polled after completion");
+            shared.queue.push_back(value);
+            shared.wake_receivers();
+            let unit = match Value::empty() {
+                VmResult::Ok(value) => value,
+                VmResult::Err(error) => return Poll::Ready(VmResult::Err(error)),
+            };
+            return Poll::Ready(Value::result(Ok(unit)));
+        }
+
+        shared.send_wakers.push(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+/// Future produced by [`Receiver::recv`].
+struct RecvFuture {
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl StdFuture for RecvFuture {
+    type Output = VmResult<Value>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut shared = self.shared.lock().unwrap();
+
+        if let Some(value) = shared.queue.pop_front() {
+            shared.wake_senders();
+            return Poll::Ready(Value::option(Some(value)));
+        }
+
+        if shared.senders == 0 {
+            return Poll::Ready(Value::option(None));
+        }
+
+        shared.recv_wakers.push(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+/// Create an unbounded channel.
+fn channel() -> VmResult<Value> {
+    make_channel(None)
+}
+
+/// Create a bounded channel with the given capacity.
+fn bounded(capacity: usize) -> VmResult<Value> {
+    make_channel(Some(capacity))
+}
+
+fn make_channel(capacity: Option<usize>) -> VmResult<Value> {
+    let shared = Shared::new(capacity);
+
+    let sender = Sender {
+        shared: shared.clone(),
+    };
+    let receiver = Receiver { shared };
+
+    VmResult::Ok(vm_try!(Value::tuple([
+        vm_try!(Value::try_from(sender)),
+        vm_try!(Value::try_from(receiver)),
+    ])))
+}
+
+/// Shared state backing a oneshot channel.
+#[derive(Debug)]
+struct OneshotShared {
+    value: Option<Value>,
+    sender: bool,
+    waker: Option<Waker>,
+}
+
+/// The sending half of a oneshot channel.
+#[derive(Debug, Any)]
+#[rune(item = ::std::channel)]
+pub struct OneshotSender {
+    shared: Arc<Mutex<OneshotShared>>,
+}
+
+impl OneshotSender {
+    /// Send the single value carried by this oneshot channel. Only the first
+    /// send is delivered; it closes the channel and later sends are ignored.
+    fn send(&self, value: Value) {
+        let mut shared = self.shared.lock().unwrap();
+        if !shared.sender {
+            return;
+        }
+        shared.value = Some(value);
+        shared.sender = false;
+        if let Some(waker) = shared.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl Drop for OneshotSender {
+    fn drop(&mut self) {
+        let mut shared = self.shared.lock().unwrap();
+        shared.sender = false;
+        if let Some(waker) = shared.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// The receiving half of a oneshot channel.
+#[derive(Debug, Any)]
+#[rune(item = ::std::channel)]
+pub struct OneshotReceiver {
+    shared: Arc<Mutex<OneshotShared>>,
+}
+
+impl OneshotReceiver {
+    /// Receive the oneshot value, resolving to `Some(value)` or `None` if the
+    /// sender was dropped without sending.
+    fn recv(&self) -> VmResult<Value> {
+        VmResult::Ok(vm_try!(Value::try_from(vm_try!(Future::new(
+            OneshotRecvFuture {
+                shared: self.shared.clone(),
+            }
+        )))))
+    }
+}
+
+/// Future produced by [`OneshotReceiver::recv`].
+struct OneshotRecvFuture {
+    shared: Arc<Mutex<OneshotShared>>,
+}
+
+impl StdFuture for OneshotRecvFuture {
+    type Output = VmResult<Value>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut shared = self.shared.lock().unwrap();
+
+        if let Some(value) = shared.value.take() {
+            return Poll::Ready(Value::option(Some(value)));
+        }
+
+        if !shared.sender {
+            return Poll::Ready(Value::option(None));
+        }
+
+        shared.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+/// Create a oneshot channel.
+fn oneshot() -> VmResult<Value> {
+    let shared = Arc::new(Mutex::new(OneshotShared {
+        value: None,
+        sender: true,
+        waker: None,
+    }));
+
+    let sender = OneshotSender {
+        shared: shared.clone(),
+    };
+    let receiver = OneshotReceiver { shared };
+
+    VmResult::Ok(vm_try!(Value::tuple([
+        vm_try!(Value::try_from(sender)),
+        vm_try!(Value::try_from(receiver)),
+    ])))
+}
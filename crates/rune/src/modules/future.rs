@@ -1,13 +1,24 @@
 //! The `std::future` module.
 
+use std::future::Future as StdFuture;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
 use crate::alloc::Vec;
-use crate::runtime::{Future, Mut, SelectFuture, Stack, Value, ValueKind, VmErrorKind, VmResult};
-use crate::{ContextError, Module};
+use crate::runtime::{
+    Future, Mut, Panic, Protocol, SelectFuture, Stack, Value, ValueKind, VmErrorKind, VmResult,
+};
+use crate::{Any, ContextError, Module};
 
 /// Construct the `std::future` module.
 pub fn module() -> Result<Module, ContextError> {
     let mut module = Module::with_crate_item("std", ["future"])?;
     module.ty::<Future>()?;
+    module.ty::<AbortHandle>()?;
+    module.ty::<Aborted>()?;
+    module.ty::<Shared>()?;
 
     module
         .raw_function("join", raw_join)
@@ -46,10 +57,117 @@ pub fn module() -> Result<Module, ContextError> {
             "```",
         ])?;
 
+    module
+        .raw_function("try_join", raw_try_join)
+        .build()?
+        .is_async(true)
+        .args(1)
+        .argument_types([None])?
+        .docs([
+            "Waits for a collection of futures producing `Result` to complete,",
+            "short-circuiting on the first `Err`.",
+            "",
+            "Behaves like [`join`][crate::future::join] on the happy path, but as",
+            "soon as any future resolves to `Err` the remaining futures are dropped",
+            "and that error is returned without waiting for them. On full success",
+            "the unwrapped `Ok` values are joined and returned wrapped in `Ok`.",
+            "",
+            "# Examples",
+            "",
+            "```rune",
+            "let a = async { Ok::<_, i64>(1) };",
+            "let b = async { Ok::<_, i64>(2) };",
+            "let (a, b) = std::future::try_join((a, b)).await?;",
+            "assert_eq!(1, a);",
+            "assert_eq!(2, b);",
+            "# Ok::<_, i64>(())",
+            "```",
+        ])?;
+
+    module
+        .raw_function("select", raw_select)
+        .build()?
+        .is_async(true)
+        .args(1)
+        .argument_types([None])?
+        .docs([
+            "Waits for the first future in a collection to complete.",
+            "",
+            "Resolves as soon as any one of the given futures completes, yielding a",
+            "pair of `(index, value)` where `index` is the position of the future",
+            "that won the race. The remaining futures are dropped.",
+            "",
+            "# Examples",
+            "",
+            "```rune",
+            "let a = async { 1 };",
+            "let b = async { 2 };",
+            "let (index, value) = std::future::select((a, b)).await;",
+            "```",
+            "",
+            "Selecting over an empty collection is an error, since there is no first",
+            "result to yield:",
+            "",
+            "```rune,should_panic",
+            "std::future::select(()).await;",
+            "```",
+        ])?;
+
+    module
+        .function("abortable", abortable)
+        .build()?
+        .args(1)
+        .argument_types([None])?
+        .docs([
+            "Wraps a future so that it can be cancelled through an abort handle.",
+            "",
+            "Returns a pair `(future, handle)`. Awaiting `future` drives the inner",
+            "future to completion as usual, but if `handle.abort()` is called the",
+            "future resolves immediately to `Err(Aborted)` the next time it is",
+            "polled, without polling the inner future again.",
+            "",
+            "# Examples",
+            "",
+            "```rune",
+            "let (future, handle) = std::future::abortable(async { 42 });",
+            "handle.abort();",
+            "assert!(future.await.is_err());",
+            "```",
+        ])?;
+
+    module.associated_function("abort", AbortHandle::abort)?;
+
+    module
+        .function("shared", shared)
+        .build()?
+        .args(1)
+        .argument_types([None])?
+        .docs([
+            "Turn a future into a cheaply cloneable handle sharing one result.",
+            "",
+            "Every clone of the returned future awaits the *same* eventual result",
+            "rather than re-running the computation. The first clone to be polled",
+            "drives the inner future to completion and caches its result; the",
+            "others read the cached value once it is ready.",
+            "",
+            "# Examples",
+            "",
+            "```rune",
+            "let a = std::future::shared(async { 42 });",
+            "let b = a.clone();",
+            "let (a, b) = std::future::join((a, b)).await;",
+            "assert_eq!(a, 42);",
+            "assert_eq!(b, 42);",
+            "```",
+        ])?;
+
+    module.associated_function("clone", Shared::clone)?;
+    module.associated_function(Protocol::INTO_FUTURE, Shared::into_future)?;
+
     Ok(module)
 }
 
-async fn try_join_impl<'a, I, F>(values: I, len: usize, factory: F) -> VmResult<Value>
+async fn join_impl<'a, I, F>(values: I, len: usize, factory: F) -> VmResult<Value>
 where
     I: IntoIterator<Item = &'a Value>,
     F: FnOnce(Vec<Value>) -> VmResult<Value>,
@@ -60,18 +178,13 @@ where
     let mut results = vm_try!(Vec::try_with_capacity(len));
 
     for (index, value) in values.into_iter().enumerate() {
-        let value = vm_try!(value.clone().into_kind_mut());
-
-        let future = Mut::try_map(value, |kind| match kind {
-            ValueKind::Future(future) => Some(future),
-            _ => None,
-        });
-
-        let future = match future {
-            Ok(future) => future,
-            Err(actual) => {
+        // Coerce awaitable values (such as `Shared`) into a `Future` through
+        // the `INTO_FUTURE` protocol, the same way `.await` does.
+        let future = match value.clone().into_future() {
+            VmResult::Ok(future) => future,
+            VmResult::Err(..) => {
                 return VmResult::err([
-                    VmErrorKind::expected::<Future>(actual.type_info()),
+                    VmErrorKind::expected::<Future>(vm_try!(value.type_info())),
                     VmErrorKind::bad_argument(index),
                 ]);
             }
@@ -89,9 +202,85 @@ where
     factory(results)
 }
 
+/// Like [`join_impl`], but each future is expected to produce a `Result`. As
+/// soon as any future yields an `Err`, the remaining futures are dropped and
+/// the error is returned without waiting for them. On full success the
+/// unwrapped `Ok` values are passed to `factory` and wrapped in `Ok`.
+async fn try_join_impl<'a, I, F>(values: I, len: usize, factory: F) -> VmResult<Value>
+where
+    I: IntoIterator<Item = &'a Value>,
+    F: FnOnce(Vec<Value>) -> VmResult<Value>,
+{
+    use futures_util::stream::StreamExt as _;
+
+    let mut futures = futures_util::stream::FuturesUnordered::new();
+    let mut results = vm_try!(Vec::try_with_capacity(len));
+
+    for (index, value) in values.into_iter().enumerate() {
+        // Coerce awaitable values (such as `Shared`) into a `Future` through
+        // the `INTO_FUTURE` protocol, the same way `.await` does.
+        let future = match value.clone().into_future() {
+            VmResult::Ok(future) => future,
+            VmResult::Err(..) => {
+                return VmResult::err([
+                    VmErrorKind::expected::<Future>(vm_try!(value.type_info())),
+                    VmErrorKind::bad_argument(index),
+                ]);
+            }
+        };
+
+        futures.push(SelectFuture::new(index, future));
+        vm_try!(results.try_push(vm_try!(Value::empty())));
+    }
+
+    while !futures.is_empty() {
+        let (index, value) = vm_try!(futures.next().await.unwrap());
+
+        let unwrapped = match &*vm_try!(value.borrow_kind_ref()) {
+            ValueKind::Result(Ok(value)) => value.clone(),
+            ValueKind::Result(Err(error)) => {
+                let error = error.clone();
+                // Short-circuit: dropping `futures` cancels the rest.
+                return VmResult::Ok(vm_try!(Value::result(Err(error))));
+            }
+            _ => {
+                return VmResult::err([
+                    VmErrorKind::bad_argument(index),
+                    VmErrorKind::expected::<crate::runtime::Result>(vm_try!(value.type_info())),
+                ]);
+            }
+        };
+
+        *results.get_mut(index).unwrap() = unwrapped;
+    }
+
+    VmResult::Ok(vm_try!(Value::result(Ok(vm_try!(factory(results))))))
+}
+
 async fn join(value: Value) -> VmResult<Value> {
     match &*vm_try!(value.borrow_kind_ref()) {
         ValueKind::EmptyTuple => VmResult::Ok(vm_try!(Value::empty())),
+        ValueKind::Tuple(tuple) => VmResult::Ok(vm_try!(
+            join_impl(tuple.iter(), tuple.len(), |vec| VmResult::Ok(vm_try!(
+                Value::tuple(vec)
+            )))
+            .await
+        )),
+        ValueKind::Vec(vec) => VmResult::Ok(vm_try!(
+            join_impl(vec.iter(), vec.len(), Value::vec).await
+        )),
+        _ => VmResult::err([
+            VmErrorKind::bad_argument(0),
+            VmErrorKind::expected::<crate::runtime::Vec>(vm_try!(value.type_info())),
+        ]),
+    }
+}
+
+async fn try_join(value: Value) -> VmResult<Value> {
+    match &*vm_try!(value.borrow_kind_ref()) {
+        ValueKind::EmptyTuple => {
+            VmResult::Ok(vm_try!(Value::result(Ok(vm_try!(Value::empty())))))
+        }
         ValueKind::Tuple(tuple) => VmResult::Ok(vm_try!(
             try_join_impl(tuple.iter(), tuple.len(), |vec| VmResult::Ok(vm_try!(
                 Value::tuple(vec)
@@ -108,6 +297,62 @@ async fn join(value: Value) -> VmResult<Value> {
     }
 }
 
+async fn select_impl<'a, I>(values: I) -> VmResult<Value>
+where
+    I: IntoIterator<Item = &'a Value>,
+{
+    use futures_util::stream::StreamExt as _;
+
+    let mut futures = futures_util::stream::FuturesUnordered::new();
+
+    for (index, value) in values.into_iter().enumerate() {
+        // Coerce awaitable values (such as `Shared`) into a `Future` through
+        // the `INTO_FUTURE` protocol, the same way `.await` does.
+        let future = match value.clone().into_future() {
+            VmResult::Ok(future) => future,
+            VmResult::Err(..) => {
+                return VmResult::err([
+                    VmErrorKind::expected::<Future>(vm_try!(value.type_info())),
+                    VmErrorKind::bad_argument(index),
+                ]);
+            }
+        };
+
+        futures.push(SelectFuture::new(index, future));
+    }
+
+    let (index, value) = match futures.next().await {
+        Some(result) => vm_try!(result),
+        None => {
+            return VmResult::err(VmErrorKind::Panic {
+                reason: Panic::custom("cannot select over an empty collection of futures"),
+            });
+        }
+    };
+
+    // Drop the losing futures without polling them to completion.
+    drop(futures);
+
+    VmResult::Ok(vm_try!(Value::tuple([
+        vm_try!(Value::from(index as i64)),
+        value
+    ])))
+}
+
+async fn select(value: Value) -> VmResult<Value> {
+    match &*vm_try!(value.borrow_kind_ref()) {
+        ValueKind::EmptyTuple => VmResult::err(VmErrorKind::Panic {
+            reason: Panic::custom("cannot select over an empty collection of futures"),
+        }),
+        ValueKind::Tuple(tuple) => VmResult::Ok(vm_try!(select_impl(tuple.iter()).await)),
+        ValueKind::Vec(vec) => VmResult::Ok(vm_try!(select_impl(vec.iter()).await)),
+        _ => VmResult::err([
+            VmErrorKind::bad_argument(0),
+            VmErrorKind::expected::<crate::runtime::Vec>(vm_try!(value.type_info())),
+        ]),
+    }
+}
+
 /// The join implementation.
 fn raw_join(stack: &mut Stack, args: usize) -> VmResult<()> {
     if args != 1 {
@@ -122,3 +367,260 @@ fn raw_join(stack: &mut Stack, args: usize) -> VmResult<()> {
     vm_try!(stack.push(future));
     VmResult::Ok(())
 }
+
+/// The try_join implementation.
+fn raw_try_join(stack: &mut Stack, args: usize) -> VmResult<()> {
+    if args != 1 {
+        return VmResult::err(VmErrorKind::BadArgumentCount {
+            actual: args,
+            expected: 1,
+        });
+    }
+
+    let value = vm_try!(stack.pop());
+    let future = vm_try!(Future::new(try_join(value)));
+    vm_try!(stack.push(future));
+    VmResult::Ok(())
+}
+
+/// The select implementation.
+fn raw_select(stack: &mut Stack, args: usize) -> VmResult<()> {
+    if args != 1 {
+        return VmResult::err(VmErrorKind::BadArgumentCount {
+            actual: args,
+            expected: 1,
+        });
+    }
+
+    let value = vm_try!(stack.pop());
+    let future = vm_try!(Future::new(select(value)));
+    vm_try!(stack.push(future));
+    VmResult::Ok(())
+}
+
+/// The error produced by an [`abortable`] future once its handle has been
+/// aborted.
+#[derive(Debug, Any)]
+#[rune(item = ::std::future)]
+pub struct Aborted;
+
+/// Shared state between an [`abortable`] future and its [`AbortHandle`].
+#[derive(Debug)]
+struct AbortInner {
+    /// Set to `true` once the future should be aborted.
+    aborted: AtomicBool,
+    /// The waker of the abortable future, if it has been polled at least once.
+    waker: Mutex<Option<Waker>>,
+}
+
+/// A handle used to abort an [`abortable`] future.
+#[derive(Debug, Any, Clone)]
+#[rune(item = ::std::future)]
+pub struct AbortHandle {
+    inner: Arc<AbortInner>,
+}
+
+impl AbortHandle {
+    /// Abort the associated future, waking it so it can resolve to
+    /// `Err(Aborted)` at the next poll.
+    fn abort(&self) {
+        self.inner.aborted.store(true, Ordering::SeqCst);
+
+        if let Some(waker) = self.inner.waker.lock().unwrap().take() {
+            waker.wake();
+        }
+    }
+}
+
+/// A future wrapping an inner [`Future`] that can be cancelled through an
+/// [`AbortHandle`]. Mirrors the way [`SelectFuture`] wraps an inner future.
+struct Abortable {
+    inner: Arc<AbortInner>,
+    future: Mut<Future>,
+}
+
+impl StdFuture for Abortable {
+    type Output = VmResult<Value>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Check the abort flag before touching the inner future.
+        if self.inner.aborted.load(Ordering::SeqCst) {
+            let aborted = match Value::try_from(Aborted) {
+                VmResult::Ok(value) => value,
+                VmResult::Err(error) => return Poll::Ready(VmResult::Err(error)),
+            };
+
+            return Poll::Ready(match Value::result(Err(aborted)) {
+                VmResult::Ok(value) => VmResult::Ok(value),
+                VmResult::Err(error) => VmResult::Err(error),
+            });
+        }
+
+        *self.inner.waker.lock().unwrap() = Some(cx.waker().clone());
+
+        let this = self.as_mut().get_mut();
+        Pin::new(&mut *this.future).poll(cx)
+    }
+}
+
+/// The abortable implementation, returning a `(future, handle)` pair.
+fn abortable(value: Value) -> VmResult<Value> {
+    let value = vm_try!(value.into_kind_mut());
+
+    let future = Mut::try_map(value, |kind| match kind {
+        ValueKind::Future(future) => Some(future),
+        _ => None,
+    });
+
+    let future = match future {
+        Ok(future) => future,
+        Err(actual) => {
+            return VmResult::err([
+                VmErrorKind::expected::<Future>(actual.type_info()),
+                VmErrorKind::bad_argument(0),
+            ]);
+        }
+    };
+
+    let inner = Arc::new(AbortInner {
+        aborted: AtomicBool::new(false),
+        waker: Mutex::new(None),
+    });
+
+    let handle = AbortHandle {
+        inner: inner.clone(),
+    };
+
+    let future = vm_try!(Future::new(Abortable { inner, future }));
+
+    VmResult::Ok(vm_try!(Value::tuple([
+        vm_try!(Value::try_from(future)),
+        vm_try!(Value::try_from(handle)),
+    ])))
+}
+
+/// Shared state backing a [`Shared`] handle and all of its clones.
+enum SharedState {
+    /// The inner future has not completed yet. `driver` is the waker of the
+    /// single clone currently driving the inner future, and `waiters` holds the
+    /// wakers of the other clones awaiting the result.
+    Pending {
+        future: Mut<Future>,
+        driver: Option<Waker>,
+        waiters: std::vec::Vec<Waker>,
+    },
+    /// The inner future completed with this value, cached for all clones.
+    Complete(Value),
+}
+
+/// A cheaply cloneable handle where every clone awaits the same result.
+///
+/// Cloning a `Shared` just bumps the reference count on the shared state, so
+/// each clone drives/reads the *same* eventual result. Awaiting a `Shared`
+/// turns it into a [`Future`] through the `INTO_FUTURE` protocol.
+#[derive(Any, Clone)]
+#[rune(item = ::std::future)]
+pub struct Shared {
+    state: Arc<Mutex<SharedState>>,
+}
+
+impl Shared {
+    /// Clone this handle, yielding another handle onto the same result.
+    fn clone(&self) -> Shared {
+        Clone::clone(self)
+    }
+
+    /// Turn this handle into a future that can be awaited.
+    fn into_future(self) -> VmResult<Value> {
+        VmResult::Ok(vm_try!(Value::try_from(vm_try!(Future::new(SharedFuture {
+            state: self.state,
+        })))))
+    }
+}
+
+/// The future produced when awaiting a [`Shared`] handle.
+struct SharedFuture {
+    state: Arc<Mutex<SharedState>>,
+}
+
+impl StdFuture for SharedFuture {
+    type Output = VmResult<Value>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.lock().unwrap();
+
+        let (future, driver, waiters) = match &mut *state {
+            // The result is already cached, hand a clone to this awaiter.
+            SharedState::Complete(value) => return Poll::Ready(VmResult::Ok(value.clone())),
+            SharedState::Pending {
+                future,
+                driver,
+                waiters,
+            } => (future, driver, waiters),
+        };
+
+        // Only the first clone to poll drives the inner future; every other
+        // clone just parks its waker and waits for the cached result.
+        match driver {
+            Some(driver) if !driver.will_wake(cx.waker()) => {
+                if !waiters.iter().any(|waiter| waiter.will_wake(cx.waker())) {
+                    waiters.push(cx.waker().clone());
+                }
+
+                return Poll::Pending;
+            }
+            driver => *driver = Some(cx.waker().clone()),
+        }
+
+        match Pin::new(&mut **future).poll(cx) {
+            Poll::Ready(result) => {
+                let value = match result {
+                    VmResult::Ok(value) => value,
+                    VmResult::Err(error) => return Poll::Ready(VmResult::Err(error)),
+                };
+
+                // Wake every other clone so they can read the cached value.
+                for waiter in waiters.drain(..) {
+                    waiter.wake();
+                }
+
+                let cloned = value.clone();
+                *state = SharedState::Complete(value);
+                Poll::Ready(VmResult::Ok(cloned))
+            }
+            // We are the driver; the inner future registered our waker, so we
+            // will be polled again without parking ourselves in `waiters`.
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// The shared implementation.
+fn shared(value: Value) -> VmResult<Value> {
+    let value = vm_try!(value.into_kind_mut());
+
+    let future = Mut::try_map(value, |kind| match kind {
+        ValueKind::Future(future) => Some(future),
+        _ => None,
+    });
+
+    let future = match future {
+        Ok(future) => future,
+        Err(actual) => {
+            return VmResult::err([
+                VmErrorKind::expected::<Future>(actual.type_info()),
+                VmErrorKind::bad_argument(0),
+            ]);
+        }
+    };
+
+    let shared = Shared {
+        state: Arc::new(Mutex::new(SharedState::Pending {
+            future,
+            driver: None,
+            waiters: std::vec::Vec::new(),
+        })),
+    };
+
+    VmResult::Ok(vm_try!(Value::try_from(shared)))
+}